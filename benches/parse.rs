@@ -0,0 +1,65 @@
+//! Times parsing a `cvarlist` capture large enough to be split into chunks, on one thread and on
+//! every thread, to show what the parallel parse gains. Run with `cargo bench --bench parse`.
+
+extern crate cvardump;
+extern crate rayon;
+use cvardump::{extract_cvars, ParseOptions};
+use std::time::{Duration, Instant};
+
+/// Rows in the generated capture, about 40 MiB of it and well above the parallel threshold.
+const ROWS: usize = 400_000;
+/// Each timing is the best of this many runs.
+const RUNS: usize = 3;
+
+fn capture() -> String {
+    let mut capture = String::from("cvarlist\n--------------\n");
+    for row in 0..ROWS {
+        capture.push_str(&format!(
+            "{:<40} : {:<8} : , \"cheat\", \"rep\" : Description of the cvar in row {}\n",
+            format!("bench_cvar_{}", row),
+            row % 100,
+            row
+        ));
+    }
+    capture.push_str(&format!("--------------\n{} total convars/concommands\n", ROWS));
+    capture
+}
+
+/// The best time to parse the capture on a pool of `threads` threads.
+fn time(capture: &str, threads: usize) -> Duration {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("Failed to build thread pool");
+    let options = ParseOptions::default();
+
+    (0..RUNS)
+        .map(|_| {
+            pool.install(|| {
+                let start = Instant::now();
+                let extracted = extract_cvars(capture, &options);
+                let elapsed = start.elapsed();
+                assert_eq!(extracted.cvars.len(), ROWS);
+                assert_eq!(extracted.expected_cvars, Some(ROWS));
+                elapsed
+            })
+        })
+        .min()
+        .expect("at least one run")
+}
+
+fn main() {
+    let capture = capture();
+    let threads = rayon::current_num_threads();
+
+    let serial = time(&capture, 1);
+    let parallel = time(&capture, threads);
+    println!("parse {} MiB, {} rows", capture.len() / (1024 * 1024), ROWS);
+    println!("  1 thread:   {:>8.1} ms", serial.as_secs_f64() * 1000.0);
+    println!(
+        "  {} threads: {:>8.1} ms, {:.1}x faster",
+        threads,
+        parallel.as_secs_f64() * 1000.0,
+        serial.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
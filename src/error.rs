@@ -0,0 +1,172 @@
+use apache_avro::error::Details;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while dumping cvars.
+#[derive(Debug)]
+pub enum CvardumpError {
+    /// Connecting to or talking with the server over RCON failed
+    Rcon(rcon::Error),
+    /// The server answered `cvarlist` with something other than a cvar list, such as an error message
+    UnexpectedResponse(String),
+    /// Reading input or writing output failed
+    Io(io::Error),
+    /// Downloading the input failed
+    Http(reqwest::Error),
+    /// A file given to cvardump, such as a fleet file, couldn't be understood
+    Parse(String),
+    /// The cvars can't be written in the chosen output format
+    Format(String),
+    /// The dump failed a check given on the command line, such as a maximum number of cvars
+    Check(String),
+    /// The run took longer than `--timeout-total` allows, holds the seconds it was given
+    Deadline(u64),
+    /// The command line asks for something that can't be done, such as options that don't go together
+    Usage(String),
+    /// The command line couldn't be read, clap knows how to report it
+    Clap(clap::Error),
+    /// The input can't be dumped as it is, such as cvars without a name under `--strict`
+    Input(String),
+    /// A step of the run failed, holds what was being done and why it failed
+    Context(String, Box<CvardumpError>),
+    /// A failure that was already reported on stderr, holds the exit code to end with
+    Reported(i32),
+}
+
+impl CvardumpError {
+    /// The exit code the program ends with because of this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CvardumpError::Io(_) => 1,
+            // A wrong password is by far the most common RCON failure, so it gets its own code
+            CvardumpError::Rcon(rcon::Error::Auth) => 7,
+            CvardumpError::Rcon(_) | CvardumpError::UnexpectedResponse(_) => 2,
+            CvardumpError::Http(_) => 3,
+            CvardumpError::Parse(_) => 4,
+            CvardumpError::Format(_) => 5,
+            CvardumpError::Check(_) => 6,
+            CvardumpError::Deadline(_) => 8,
+            CvardumpError::Usage(_) | CvardumpError::Clap(_) | CvardumpError::Input(_) => 1,
+            // The cause decides, "Failed to read input" is still an RCON or HTTP problem
+            CvardumpError::Context(_, err) => err.exit_code(),
+            CvardumpError::Reported(code) => *code,
+        }
+    }
+}
+
+impl fmt::Display for CvardumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CvardumpError::Rcon(rcon::Error::Auth) => write!(f, "RCON authentication failed, check your password"),
+            CvardumpError::Rcon(err) => write!(f, "RCON error: {}", err),
+            CvardumpError::UnexpectedResponse(response) => {
+                write!(f, "The server didn't answer with a cvar list, it said:\n\n{}", response)
+            }
+            CvardumpError::Io(err) => write!(f, "{}", err),
+            CvardumpError::Http(err) => write!(f, "{}", err),
+            CvardumpError::Parse(message) => write!(f, "{}", message),
+            CvardumpError::Format(message) => write!(f, "{}", message),
+            CvardumpError::Check(message) => write!(f, "{}", message),
+            CvardumpError::Deadline(seconds) => write!(f, "Stopped after the --timeout-total of {} seconds", seconds),
+            CvardumpError::Usage(message) => write!(f, "{}", message),
+            CvardumpError::Clap(err) => write!(f, "{}", err),
+            CvardumpError::Input(message) => write!(f, "{}", message),
+            CvardumpError::Context(action, err) => write!(f, "{}\n\n{}", action, err),
+            CvardumpError::Reported(code) => write!(f, "Failed with exit code {}", code),
+        }
+    }
+}
+
+impl Error for CvardumpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CvardumpError::Rcon(err) => Some(err),
+            CvardumpError::Io(err) => Some(err),
+            CvardumpError::Http(err) => Some(err),
+            CvardumpError::Clap(err) => Some(err),
+            CvardumpError::Context(_, err) => Some(err.as_ref()),
+            CvardumpError::UnexpectedResponse(_)
+            | CvardumpError::Parse(_)
+            | CvardumpError::Format(_)
+            | CvardumpError::Check(_)
+            | CvardumpError::Deadline(_)
+            | CvardumpError::Usage(_)
+            | CvardumpError::Input(_)
+            | CvardumpError::Reported(_) => None,
+        }
+    }
+}
+
+impl From<clap::Error> for CvardumpError {
+    fn from(err: clap::Error) -> Self {
+        CvardumpError::Clap(err)
+    }
+}
+
+impl From<rcon::Error> for CvardumpError {
+    fn from(err: rcon::Error) -> Self {
+        CvardumpError::Rcon(err)
+    }
+}
+
+impl From<io::Error> for CvardumpError {
+    fn from(err: io::Error) -> Self {
+        CvardumpError::Io(err)
+    }
+}
+
+impl From<reqwest::Error> for CvardumpError {
+    fn from(err: reqwest::Error) -> Self {
+        CvardumpError::Http(err)
+    }
+}
+
+impl From<csv::Error> for CvardumpError {
+    fn from(err: csv::Error) -> Self {
+        if err.is_io_error() {
+            match err.into_kind() {
+                csv::ErrorKind::Io(err) => CvardumpError::Io(err),
+                _ => unreachable!(),
+            }
+        } else {
+            CvardumpError::Format(err.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for CvardumpError {
+    fn from(err: serde_json::Error) -> Self {
+        if err.is_io() {
+            CvardumpError::Io(err.into())
+        } else {
+            CvardumpError::Format(err.to_string())
+        }
+    }
+}
+
+impl From<apache_avro::Error> for CvardumpError {
+    fn from(err: apache_avro::Error) -> Self {
+        match err.into_details() {
+            Details::WriteBytes(err) | Details::FlushWriter(err) | Details::WriteMarker(err) => CvardumpError::Io(err),
+            details => CvardumpError::Format(apache_avro::Error::new(details).to_string()),
+        }
+    }
+}
+
+impl From<toml::de::Error> for CvardumpError {
+    fn from(err: toml::de::Error) -> Self {
+        CvardumpError::Parse(err.to_string())
+    }
+}
+
+/// Says what was being done when an error happened, for reporting it as "Failed to ..." with the cause below.
+pub trait Context<T> {
+    fn context(self, action: &str) -> Result<T, CvardumpError>;
+}
+
+impl<T, E: Into<CvardumpError>> Context<T> for Result<T, E> {
+    fn context(self, action: &str) -> Result<T, CvardumpError> {
+        self.map_err(|err| CvardumpError::Context(action.to_string(), Box::new(err.into())))
+    }
+}